@@ -3,9 +3,10 @@ use {
     crate::geyser_plugin_manager::GeyserPluginManager,
     agave_geyser_plugin_interface::geyser_plugin_interface::{
         ReplicaAccountInfoV3, ReplicaAccountInfoVersions, ReplicaTransactionAccountsInfo,
-        ReplicaTransactionAccountsInfoVersions,
+        ReplicaTransactionAccountsInfoVersions, ReplicaTransactionErrorInfo,
+        ReplicaTransactionErrorInfoVersions,
     },
-    crossbeam_channel::{bounded, Receiver, Sender, TrySendError},
+    crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError},
     log::*,
     solana_account::{AccountSharedData, ReadableAccount},
     solana_accounts_db::accounts_update_notifier_interface::{
@@ -17,10 +18,16 @@ use {
     solana_pubkey::Pubkey,
     solana_signature::Signature,
     solana_transaction::sanitized::SanitizedTransaction,
+    solana_transaction_error::TransactionError,
     std::{
-        sync::{Arc, Mutex, RwLock},
+        collections::{hash_map::DefaultHasher, HashMap},
+        hash::{Hash, Hasher},
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex, RwLock,
+        },
         thread::{Builder, JoinHandle},
-        time::Instant,
+        time::{Duration, Instant},
     },
 };
 #[derive(Debug)]
@@ -29,10 +36,90 @@ pub(crate) struct AccountsUpdateNotifierImpl {
     snapshot_notifications_enabled: bool,
     async_dispatch: Option<AsyncAccountsDispatch>,
     enable_transaction_accounts_notify: bool,
+    enable_transaction_error_notify: bool,
+    restore_stats: GeyserPluginNotifyAtSnapshotRestoreStats,
 }
 
 const ASYNC_ACCOUNTS_DISPATCH_CHANNEL_CAPACITY: usize = 16_384;
 
+const RESTORE_STATS_SHARD_COUNT: usize = 8;
+
+#[derive(Debug, Default)]
+struct RestoreStatsShard {
+    accounts_notified_count: AtomicU64,
+    zero_lamport_count: AtomicU64,
+    filtering_us: AtomicU64,
+    notifying_us: AtomicU64,
+}
+
+/// Accumulates counts and timings across an entire snapshot restore so that
+/// `notify_end_of_restore_from_snapshot` can report one summary datapoint
+/// instead of leaving operators to eyeball per-account debug counters.
+///
+/// Restores drive `notify_account_restore_from_snapshot` from many threads
+/// across ~1B accounts, so these are atomics rather than a mutex-guarded
+/// struct: a lock taken per account would serialize the very threads the
+/// parallel restore relies on. A *single* shared `AtomicU64` per field would
+/// still have every core fighting over the same cache line on every account,
+/// so counts are spread across a handful of shards (picked by the calling
+/// thread) and summed once in `take`.
+///
+/// Nothing is actually skipped on this path — every restored account,
+/// zero-lamport or not, is notified — so there is no "accounts skipped"
+/// count to report; `zero_lamport_count` instead surfaces how many of the
+/// notified accounts were zero-lamport tombstones, as informational context.
+#[derive(Debug)]
+struct GeyserPluginNotifyAtSnapshotRestoreStats {
+    shards: [RestoreStatsShard; RESTORE_STATS_SHARD_COUNT],
+}
+
+impl Default for GeyserPluginNotifyAtSnapshotRestoreStats {
+    fn default() -> Self {
+        Self {
+            shards: std::array::from_fn(|_| RestoreStatsShard::default()),
+        }
+    }
+}
+
+impl GeyserPluginNotifyAtSnapshotRestoreStats {
+    /// Picks a shard based on the calling thread, so concurrent restore
+    /// threads mostly update independent cache lines.
+    fn shard(&self) -> &RestoreStatsShard {
+        let mut hasher = DefaultHasher::new();
+        std::thread::current().id().hash(&mut hasher);
+        let index = (hasher.finish() % RESTORE_STATS_SHARD_COUNT as u64) as usize;
+        &self.shards[index]
+    }
+
+    /// Reads and resets every counter across all shards, so the next restore
+    /// starts from zero.
+    fn take(&self) -> (u64, u64, u64, u64) {
+        self.shards.iter().fold((0, 0, 0, 0), |acc, shard| {
+            (
+                acc.0 + shard.accounts_notified_count.swap(0, Ordering::Relaxed),
+                acc.1 + shard.zero_lamport_count.swap(0, Ordering::Relaxed),
+                acc.2 + shard.filtering_us.swap(0, Ordering::Relaxed),
+                acc.3 + shard.notifying_us.swap(0, Ordering::Relaxed),
+            )
+        })
+    }
+}
+
+// Sharding the dispatch threads keeps one slow plugin from serializing every
+// account notification behind a single queue, while hashing on `pubkey`
+// still guarantees per-account `write_version` ordering within its shard.
+const DEFAULT_ASYNC_DISPATCH_SHARD_DIVISOR: usize = 4;
+
+/// Picks a small fraction of the available cores as the default shard count,
+/// so the dispatch threads don't compete with replay/accounts-db for CPU.
+/// Used as the fallback when `AccountsUpdateNotifierImpl::new` isn't given an
+/// explicit `async_dispatch_shard_count`.
+fn default_async_dispatch_shard_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|cores| (cores.get() / DEFAULT_ASYNC_DISPATCH_SHARD_DIVISOR).max(1))
+        .unwrap_or(1)
+}
+
 #[derive(Debug)]
 struct QueuedAccountUpdate {
     slot: Slot,
@@ -44,20 +131,125 @@ struct QueuedAccountUpdate {
     enqueue_at: Instant,
 }
 
+#[derive(Debug)]
+struct QueuedTransactionError {
+    slot: Slot,
+    signature: Signature,
+    transaction: SanitizedTransaction,
+    error: TransactionError,
+    enqueue_at: Instant,
+}
+
 #[derive(Debug)]
 enum DispatchMessage {
     Account(QueuedAccountUpdate),
+    TransactionError(QueuedTransactionError),
+}
+
+// Bounds on how long a batch of account updates sits in the worker before
+// being flushed to plugins, trading a little latency for far fewer
+// `plugin_manager` lock acquisitions and plugin dispatch calls.
+const ACCOUNT_UPDATE_BATCH_SIZE: usize = 1024;
+const ACCOUNT_UPDATE_BATCH_WINDOW: Duration = Duration::from_millis(5);
+
+/// Accumulates same-`(slot, is_startup)` account updates for a single
+/// dispatch worker until a size or time threshold is hit.
+#[derive(Default)]
+struct AccountUpdateBatcher {
+    pending: Vec<QueuedAccountUpdate>,
+    batch_key: Option<(Slot, bool)>,
+    oldest_enqueued_at: Option<Instant>,
+}
+
+impl AccountUpdateBatcher {
+    /// Adds `update` to the pending batch, returning a flushed batch if this
+    /// push crossed the size threshold or started a new `(slot, is_startup)`
+    /// group (in which case the *previous* group is flushed).
+    fn push(&mut self, update: QueuedAccountUpdate) -> Option<Vec<QueuedAccountUpdate>> {
+        let key = (update.slot, update.is_startup);
+        let flushed = if self.batch_key.is_some_and(|existing| existing != key) {
+            self.take()
+        } else {
+            None
+        };
+
+        if self.batch_key.is_none() {
+            self.batch_key = Some(key);
+            self.oldest_enqueued_at = Some(Instant::now());
+        }
+        self.pending.push(update);
+
+        if flushed.is_some() {
+            flushed
+        } else if self.pending.len() >= ACCOUNT_UPDATE_BATCH_SIZE {
+            self.take()
+        } else {
+            None
+        }
+    }
+
+    /// Flushes the pending batch if it's been open at least
+    /// `ACCOUNT_UPDATE_BATCH_WINDOW`, so a slow trickle of updates still gets
+    /// delivered promptly instead of waiting for the size threshold.
+    fn take_if_stale(&mut self) -> Option<Vec<QueuedAccountUpdate>> {
+        if self
+            .oldest_enqueued_at
+            .is_some_and(|at| at.elapsed() >= ACCOUNT_UPDATE_BATCH_WINDOW)
+        {
+            self.take()
+        } else {
+            None
+        }
+    }
+
+    fn take(&mut self) -> Option<Vec<QueuedAccountUpdate>> {
+        self.batch_key = None;
+        self.oldest_enqueued_at = None;
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut self.pending))
+        }
+    }
 }
 
 #[derive(Debug)]
-struct AsyncAccountsDispatch {
+struct DispatchShard {
     sender: Mutex<Option<Sender<DispatchMessage>>>,
     thread_hdl: Mutex<Option<JoinHandle<()>>>,
 }
 
+#[derive(Debug)]
+struct AsyncAccountsDispatch {
+    shards: Vec<DispatchShard>,
+}
+
 impl AsyncAccountsDispatch {
-    fn try_send(&self, message: DispatchMessage) -> Result<(), TrySendError<DispatchMessage>> {
-        let sender = self.sender.lock().unwrap();
+    fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Hashes `pubkey` to a shard index so that updates to the same account
+    /// are always routed to (and ordered within) the same worker thread.
+    fn shard_for_pubkey(pubkey: &Pubkey, num_shards: usize) -> usize {
+        Self::shard_for_bytes(pubkey.as_ref(), num_shards)
+    }
+
+    /// Hashes the leading 8 bytes of `key` to a shard index. Used for anything
+    /// keyed by a fixed-size identifier (32-byte pubkeys, 64-byte signatures) that doesn't need
+    /// ordering guarantees across different keys.
+    fn shard_for_bytes(key: &[u8], num_shards: usize) -> usize {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&key[0..8]);
+        (u64::from_le_bytes(bytes) % num_shards as u64) as usize
+    }
+
+    fn try_send(
+        &self,
+        shard_index: usize,
+        message: DispatchMessage,
+    ) -> Result<(), TrySendError<DispatchMessage>> {
+        let sender = self.shards[shard_index].sender.lock().unwrap();
         if let Some(sender) = sender.as_ref() {
             sender.try_send(message)
         } else {
@@ -66,10 +258,15 @@ impl AsyncAccountsDispatch {
     }
 
     fn stop(&self) {
-        // Drop sender first so receiver exits after draining queued work.
-        self.sender.lock().unwrap().take();
-        if let Some(thread_hdl) = self.thread_hdl.lock().unwrap().take() {
-            let _ = thread_hdl.join();
+        // Drop every sender first so each worker exits after draining its
+        // own queued work, then join the now-unblocked threads.
+        for shard in &self.shards {
+            shard.sender.lock().unwrap().take();
+        }
+        for shard in &self.shards {
+            if let Some(thread_hdl) = shard.thread_hdl.lock().unwrap().take() {
+                let _ = thread_hdl.join();
+            }
         }
     }
 }
@@ -88,6 +285,8 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         write_version: u64,
     ) {
         if let Some(async_dispatch) = &self.async_dispatch {
+            let shard_index =
+                AsyncAccountsDispatch::shard_for_pubkey(pubkey, async_dispatch.shard_count());
             let message = DispatchMessage::Account(QueuedAccountUpdate {
                 slot,
                 pubkey: *pubkey,
@@ -97,16 +296,25 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
                 is_startup: false,
                 enqueue_at: Instant::now(),
             });
-            match async_dispatch.try_send(message) {
+            match async_dispatch.try_send(shard_index, message) {
                 Ok(()) => {
                     inc_new_counter_debug!("geyser-plugin-async-account-dispatch-queued", 1);
                     return;
                 }
                 Err(TrySendError::Full(_)) => {
-                    inc_new_counter_warn!("geyser-plugin-async-account-dispatch-overflow", 1);
+                    // `inc_new_counter_*!` backs each name with a `'static`
+                    // counter, so the shard can't be baked into the name via
+                    // `format!`; tag it on a datapoint field instead.
+                    datapoint_warn!(
+                        "geyser-plugin-async-account-dispatch-overflow",
+                        ("shard", shard_index, i64),
+                    );
                 }
                 Err(TrySendError::Disconnected(_)) => {
-                    inc_new_counter_warn!("geyser-plugin-async-account-dispatch-disconnected", 1);
+                    datapoint_warn!(
+                        "geyser-plugin-async-account-dispatch-disconnected",
+                        ("shard", shard_index, i64),
+                    );
                 }
             }
         }
@@ -129,6 +337,7 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
 
         let mut account = self.accountinfo_from_account_for_geyser(account);
         account.write_version = write_version;
+        let is_zero_lamport = account.lamports == 0;
         let time_copy = log_enabled!(Level::Debug).then(|| start.unwrap().elapsed());
 
         self.notify_plugins_of_account_update(account, slot, true);
@@ -148,6 +357,28 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
             100000,
             100000
         );
+
+        // All restored accounts are notified, including zero-lamport ones, so
+        // they all count; `zero_lamport_count` is additional context, not a
+        // carve-out from `accounts_notified_count`.
+        let stats_shard = self.restore_stats.shard();
+        stats_shard
+            .accounts_notified_count
+            .fetch_add(1, Ordering::Relaxed);
+        if is_zero_lamport {
+            stats_shard
+                .zero_lamport_count
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        if let (Some(time_copy), Some(time_all)) = (time_copy, time_all) {
+            stats_shard
+                .filtering_us
+                .fetch_add(time_copy.as_micros() as u64, Ordering::Relaxed);
+            stats_shard.notifying_us.fetch_add(
+                time_all.saturating_sub(time_copy).as_micros() as u64,
+                Ordering::Relaxed,
+            );
+        }
     }
 
     fn notify_end_of_restore_from_snapshot(&self) {
@@ -179,6 +410,16 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
                 measure.as_us() as usize
             );
         }
+
+        let (accounts_notified_count, zero_lamport_count, filtering_us, notifying_us) =
+            self.restore_stats.take();
+        datapoint_info!(
+            "geyser_plugin_notify_account_restore_from_snapshot_summary",
+            ("accounts_notified_count", accounts_notified_count, i64),
+            ("zero_lamport_count", zero_lamport_count, i64),
+            ("filtering_us", filtering_us, i64),
+            ("notifying_us", notifying_us, i64),
+        );
     }
 
     fn notify_transaction_accounts(
@@ -283,26 +524,116 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         owners.dedup();
         owners
     }
+
+    fn notify_transaction_error(
+        &self,
+        slot: Slot,
+        signature: &Signature,
+        transaction: &SanitizedTransaction,
+        error: &TransactionError,
+    ) {
+        if !self.enable_transaction_error_notify {
+            return;
+        }
+
+        if let Some(async_dispatch) = &self.async_dispatch {
+            let shard_index = AsyncAccountsDispatch::shard_for_bytes(
+                signature.as_ref(),
+                async_dispatch.shard_count(),
+            );
+            let message = DispatchMessage::TransactionError(QueuedTransactionError {
+                slot,
+                signature: *signature,
+                transaction: transaction.clone(),
+                error: error.clone(),
+                enqueue_at: Instant::now(),
+            });
+            match async_dispatch.try_send(shard_index, message) {
+                Ok(()) => return,
+                // Distinct names from the account-dispatch overflow/disconnect
+                // datapoints above: these reflect a dropped transaction-error
+                // notification, not a dropped account update.
+                Err(TrySendError::Full(_)) => {
+                    datapoint_warn!(
+                        "geyser-plugin-async-transaction-error-dispatch-overflow",
+                        ("shard", shard_index, i64),
+                    );
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    datapoint_warn!(
+                        "geyser-plugin-async-transaction-error-dispatch-disconnected",
+                        ("shard", shard_index, i64),
+                    );
+                }
+            }
+        }
+
+        Self::notify_plugins_of_transaction_error(
+            &self.plugin_manager,
+            slot,
+            signature,
+            transaction,
+            error,
+        );
+    }
+
+    fn transaction_error_notifications_enabled(&self) -> bool {
+        if !self.enable_transaction_error_notify {
+            return false;
+        }
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        plugin_manager
+            .plugins
+            .iter()
+            .any(|plugin| plugin.transaction_error_notifications_enabled())
+    }
 }
 
 impl AccountsUpdateNotifierImpl {
+    /// `accounts_notify_async_coalesced`: when async dispatch is on, each
+    /// worker stages queued account updates and keeps only the highest
+    /// `write_version` per account per slot (see `run_async_dispatch_coalesced`).
+    ///
+    /// `enable_transaction_error_notify`: gates `notify_transaction_error`.
+    ///
+    /// `async_dispatch_shard_count`: overrides `default_async_dispatch_shard_count()`
+    /// when set (e.g. from a validator CLI flag); `None` keeps the
+    /// core-fraction default.
     pub fn new(
         plugin_manager: Arc<RwLock<GeyserPluginManager>>,
         snapshot_notifications_enabled: bool,
         accounts_notify_async: bool,
+        async_dispatch_shard_count: Option<usize>,
+        accounts_notify_async_coalesced: bool,
         enable_transaction_accounts_notify: bool,
+        enable_transaction_error_notify: bool,
     ) -> Self {
         let async_dispatch = accounts_notify_async.then(|| {
-            let (sender, receiver) = bounded(ASYNC_ACCOUNTS_DISPATCH_CHANNEL_CAPACITY);
-            let plugin_manager = plugin_manager.clone();
-            let thread_hdl = Builder::new()
-                .name("solGeyserAcctAsync".to_string())
-                .spawn(move || Self::run_async_dispatch(receiver, plugin_manager))
-                .expect("spawn geyser async account notifier");
-            AsyncAccountsDispatch {
-                sender: Mutex::new(Some(sender)),
-                thread_hdl: Mutex::new(Some(thread_hdl)),
-            }
+            let num_shards = async_dispatch_shard_count
+                .unwrap_or_else(default_async_dispatch_shard_count)
+                .max(1);
+            let shards = (0..num_shards)
+                .map(|shard_index| {
+                    let (sender, receiver) = bounded(ASYNC_ACCOUNTS_DISPATCH_CHANNEL_CAPACITY);
+                    let plugin_manager = plugin_manager.clone();
+                    let thread_hdl = Builder::new()
+                        .name(format!("solGeyserAcctAsync{shard_index}"))
+                        .spawn(move || {
+                            Self::run_async_dispatch(
+                                shard_index,
+                                receiver,
+                                plugin_manager,
+                                accounts_notify_async_coalesced,
+                            )
+                        })
+                        .expect("spawn geyser async account notifier");
+                    DispatchShard {
+                        sender: Mutex::new(Some(sender)),
+                        thread_hdl: Mutex::new(Some(thread_hdl)),
+                    }
+                })
+                .collect();
+            AsyncAccountsDispatch { shards }
         });
 
         AccountsUpdateNotifierImpl {
@@ -310,42 +641,304 @@ impl AccountsUpdateNotifierImpl {
             snapshot_notifications_enabled,
             async_dispatch,
             enable_transaction_accounts_notify,
+            enable_transaction_error_notify,
+            restore_stats: GeyserPluginNotifyAtSnapshotRestoreStats::default(),
         }
     }
 
     fn run_async_dispatch(
+        shard_index: usize,
         receiver: Receiver<DispatchMessage>,
         plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+        coalesce: bool,
     ) {
+        if coalesce {
+            Self::run_async_dispatch_coalesced(shard_index, receiver, plugin_manager);
+        } else {
+            let mut batcher = AccountUpdateBatcher::default();
+            loop {
+                match receiver.recv_timeout(ACCOUNT_UPDATE_BATCH_WINDOW) {
+                    Ok(DispatchMessage::Account(update)) => {
+                        if let Some(batch) = batcher.push(update) {
+                            Self::dispatch_account_batch(shard_index, &plugin_manager, batch);
+                        }
+                        // A steady stream never hits the recv timeout below,
+                        // so check the window here as well.
+                        if let Some(batch) = batcher.take_if_stale() {
+                            Self::dispatch_account_batch(shard_index, &plugin_manager, batch);
+                        }
+                    }
+                    Ok(DispatchMessage::TransactionError(update)) => {
+                        // Flush first so accounts already queued for this
+                        // worker are notified before the error that (maybe)
+                        // followed them, preserving arrival order.
+                        if let Some(batch) = batcher.take() {
+                            Self::dispatch_account_batch(shard_index, &plugin_manager, batch);
+                        }
+                        Self::dispatch_transaction_error(&plugin_manager, update);
+                    }
+                    Err(RecvTimeoutError::Timeout) => {
+                        if let Some(batch) = batcher.take_if_stale() {
+                            Self::dispatch_account_batch(shard_index, &plugin_manager, batch);
+                        }
+                    }
+                    Err(RecvTimeoutError::Disconnected) => {
+                        if let Some(batch) = batcher.take() {
+                            Self::dispatch_account_batch(shard_index, &plugin_manager, batch);
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Like `run_async_dispatch`, but stages account updates in a
+    /// `(pubkey, slot)` keyed map, so that the updates already queued when the
+    /// worker drains the channel collapse to the last `write_version` per
+    /// account per slot. The channel in front of the map is still bounded:
+    /// when it is full, `notify_account_update` delivers the update
+    /// synchronously in the caller, as it does without coalescing.
+    fn run_async_dispatch_coalesced(
+        shard_index: usize,
+        receiver: Receiver<DispatchMessage>,
+        plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+    ) {
+        let mut staged: HashMap<(Pubkey, Slot), QueuedAccountUpdate> = HashMap::new();
         while let Ok(message) = receiver.recv() {
-            match message {
-                DispatchMessage::Account(update) => {
-                    inc_new_counter_debug!(
-                        "geyser-plugin-async-account-dispatch-latency-us",
-                        update.enqueue_at.elapsed().as_micros() as usize,
-                        100000,
-                        100000
-                    );
-                    let account_info = ReplicaAccountInfoV3 {
-                        pubkey: update.pubkey.as_ref(),
-                        lamports: update.account.lamports(),
-                        owner: update.account.owner().as_ref(),
-                        executable: update.account.executable(),
-                        rent_epoch: update.account.rent_epoch(),
-                        data: update.account.data(),
-                        write_version: update.write_version,
-                        txn: update.txn.as_ref(),
-                    };
-                    Self::notify_plugins_of_account_update_inner(
-                        &plugin_manager,
-                        account_info,
-                        update.slot,
-                        update.is_startup,
+            Self::stage_or_dispatch(shard_index, &plugin_manager, &mut staged, message);
+            // Drain whatever else is already queued before flushing, so that
+            // several writes to the same account piled up behind a slow
+            // plugin are coalesced into one notification.
+            while let Ok(message) = receiver.try_recv() {
+                Self::stage_or_dispatch(shard_index, &plugin_manager, &mut staged, message);
+            }
+            Self::flush_staged(shard_index, &plugin_manager, &mut staged);
+        }
+    }
+
+    /// Drains `staged` and dispatches it, ordered by `(slot, write_version)`.
+    /// A `HashMap` drain has no defined order, but two writes to the same
+    /// account across different slots can both be staged in one drain burst,
+    /// so without this sort a newer slot could be delivered before an older
+    /// one — breaking the write-version ordering the sharding in
+    /// `notify_account_update` otherwise guarantees.
+    fn flush_staged(
+        shard_index: usize,
+        plugin_manager: &Arc<RwLock<GeyserPluginManager>>,
+        staged: &mut HashMap<(Pubkey, Slot), QueuedAccountUpdate>,
+    ) {
+        let drained: Vec<QueuedAccountUpdate> = staged.drain().map(|(_, v)| v).collect();
+        for (_, _, batch) in Self::order_and_group_for_flush(drained) {
+            Self::dispatch_account_batch(shard_index, plugin_manager, batch);
+        }
+    }
+
+    /// Sorts `updates` by `(slot, write_version)` before grouping, so that a
+    /// `HashMap` drain's arbitrary order can never reorder two writes to the
+    /// same account that landed in the same drain burst.
+    fn order_and_group_for_flush(
+        mut updates: Vec<QueuedAccountUpdate>,
+    ) -> Vec<(Slot, bool, Vec<QueuedAccountUpdate>)> {
+        updates.sort_by_key(|update| (update.slot, update.write_version));
+        Self::group_by_batch_key(updates)
+    }
+
+    fn stage_or_dispatch(
+        shard_index: usize,
+        plugin_manager: &Arc<RwLock<GeyserPluginManager>>,
+        staged: &mut HashMap<(Pubkey, Slot), QueuedAccountUpdate>,
+        message: DispatchMessage,
+    ) {
+        match message {
+            DispatchMessage::Account(update) => {
+                if update.is_startup {
+                    // Startup (snapshot-restore) updates must never be
+                    // dropped, so they bypass coalescing entirely.
+                    Self::dispatch_account_batch(shard_index, plugin_manager, vec![update]);
+                    return;
+                }
+                let key = (update.pubkey, update.slot);
+                match staged.get(&key) {
+                    Some(existing) if existing.write_version > update.write_version => {
+                        datapoint_debug!(
+                            "geyser-plugin-async-account-coalesced",
+                            ("shard", shard_index, i64),
+                        );
+                    }
+                    _ => {
+                        if staged.insert(key, update).is_some() {
+                            datapoint_debug!(
+                                "geyser-plugin-async-account-coalesced",
+                                ("shard", shard_index, i64),
+                            );
+                        }
+                    }
+                }
+            }
+            // Transaction errors aren't keyed by (pubkey, slot) and each one
+            // is distinct, so they're never coalesced. Flush whatever's
+            // already staged first so earlier-queued account writes reach
+            // plugins before this error, matching the arrival-order
+            // guarantee the non-coalesced worker gives.
+            DispatchMessage::TransactionError(update) => {
+                Self::flush_staged(shard_index, plugin_manager, staged);
+                Self::dispatch_transaction_error(plugin_manager, update);
+            }
+        }
+    }
+
+    /// Splits a flushed batch of updates into per-`(slot, is_startup)` groups,
+    /// preserving the relative arrival order of accounts within each group,
+    /// since `update_accounts` reports a single slot/`is_startup` per call.
+    fn group_by_batch_key(
+        updates: Vec<QueuedAccountUpdate>,
+    ) -> Vec<(Slot, bool, Vec<QueuedAccountUpdate>)> {
+        let mut groups: Vec<(Slot, bool, Vec<QueuedAccountUpdate>)> = Vec::new();
+        'updates: for update in updates {
+            let key = (update.slot, update.is_startup);
+            for group in groups.iter_mut() {
+                if (group.0, group.1) == key {
+                    group.2.push(update);
+                    continue 'updates;
+                }
+            }
+            groups.push((key.0, key.1, vec![update]));
+        }
+        groups
+    }
+
+    fn dispatch_transaction_error(
+        plugin_manager: &Arc<RwLock<GeyserPluginManager>>,
+        update: QueuedTransactionError,
+    ) {
+        inc_new_counter_debug!(
+            "geyser-plugin-async-transaction-error-dispatch-latency-us",
+            update.enqueue_at.elapsed().as_micros() as usize,
+            100000,
+            100000
+        );
+        Self::notify_plugins_of_transaction_error(
+            plugin_manager,
+            update.slot,
+            &update.signature,
+            &update.transaction,
+            &update.error,
+        );
+        inc_new_counter_debug!("geyser-plugin-async-transaction-error-dispatch-drained", 1);
+    }
+
+    fn notify_plugins_of_transaction_error(
+        plugin_manager: &Arc<RwLock<GeyserPluginManager>>,
+        slot: Slot,
+        signature: &Signature,
+        transaction: &SanitizedTransaction,
+        error: &TransactionError,
+    ) {
+        let plugin_manager = plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let error = error.to_string();
+        let error_info = ReplicaTransactionErrorInfo {
+            signature,
+            slot,
+            transaction,
+            error: &error,
+        };
+
+        for plugin in plugin_manager.plugins.iter() {
+            if !plugin.transaction_error_notifications_enabled() {
+                continue;
+            }
+
+            let mut measure = Measure::start("geyser-plugin-notify-transaction-error");
+            match plugin
+                .notify_transaction_error(ReplicaTransactionErrorInfoVersions::V0_0_1(&error_info))
+            {
+                Err(err) => {
+                    error!(
+                        "Failed to notify transaction error for signature {} at slot {}, error: {} to plugin {}",
+                        signature,
+                        slot,
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully notified transaction error for signature {} at slot {} to plugin {}",
+                        signature,
+                        slot,
+                        plugin.name()
                     );
-                    inc_new_counter_debug!("geyser-plugin-async-account-dispatch-drained", 1);
                 }
             }
+            measure.stop();
+            inc_new_counter_debug!(
+                "geyser-plugin-notify-transaction-error-us",
+                measure.as_us() as usize,
+                100000,
+                100000
+            );
+        }
+    }
+
+    /// Notifies plugins of a batch of accounts belonging to the same slot
+    /// under a single `plugin_manager` read lock, amortizing the per-account
+    /// lock/dispatch overhead that dominates at startup.
+    fn dispatch_account_batch(
+        shard_index: usize,
+        plugin_manager: &Arc<RwLock<GeyserPluginManager>>,
+        batch: Vec<QueuedAccountUpdate>,
+    ) {
+        let Some(first) = batch.first() else {
+            return;
+        };
+        let slot = first.slot;
+        let is_startup = first.is_startup;
+
+        for update in &batch {
+            // `inc_new_counter_*!`'s histogram form needs a `'static` name,
+            // so the shard can't be interpolated into it; tag it instead.
+            datapoint_debug!(
+                "geyser-plugin-async-account-dispatch-latency-us",
+                ("shard", shard_index, i64),
+                (
+                    "latency_us",
+                    update.enqueue_at.elapsed().as_micros() as i64,
+                    i64
+                ),
+            );
         }
+
+        let account_infos: Vec<ReplicaAccountInfoV3> = batch
+            .iter()
+            .map(|update| ReplicaAccountInfoV3 {
+                pubkey: update.pubkey.as_ref(),
+                lamports: update.account.lamports(),
+                owner: update.account.owner().as_ref(),
+                executable: update.account.executable(),
+                rent_epoch: update.account.rent_epoch(),
+                data: update.account.data(),
+                write_version: update.write_version,
+                txn: update.txn.as_ref(),
+            })
+            .collect();
+
+        Self::notify_plugins_of_account_update_batch(
+            plugin_manager,
+            &account_infos,
+            slot,
+            is_startup,
+        );
+
+        datapoint_debug!(
+            "geyser-plugin-async-account-dispatch-drained",
+            ("shard", shard_index, i64),
+            ("count", account_infos.len() as i64, i64),
+        );
     }
 
     fn accountinfo_from_shared_account_data<'a>(
@@ -450,6 +1043,64 @@ impl AccountsUpdateNotifierImpl {
             100000
         );
     }
+
+    /// Batched counterpart of `notify_plugins_of_account_update_inner`: takes
+    /// the `plugin_manager` read lock once for the whole slice instead of
+    /// once per account. Plugins that haven't implemented `update_accounts`
+    /// still get notified account-by-account via its default implementation.
+    fn notify_plugins_of_account_update_batch(
+        plugin_manager: &Arc<RwLock<GeyserPluginManager>>,
+        accounts: &[ReplicaAccountInfoV3],
+        slot: Slot,
+        is_startup: bool,
+    ) {
+        if accounts.is_empty() {
+            return;
+        }
+
+        let mut measure2 = Measure::start("geyser-plugin-notify_plugins_of_account_update_batch");
+        let plugin_manager = plugin_manager.read().unwrap();
+
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+        for plugin in plugin_manager.plugins.iter() {
+            let mut measure = Measure::start("geyser-plugin-update-accounts");
+            match plugin.update_accounts(accounts, slot, is_startup) {
+                Err(err) => {
+                    error!(
+                        "Failed to update {} accounts at slot {}, error: {} to plugin {}",
+                        accounts.len(),
+                        slot,
+                        err,
+                        plugin.name()
+                    )
+                }
+                Ok(_) => {
+                    trace!(
+                        "Successfully updated {} accounts at slot {} to plugin {}",
+                        accounts.len(),
+                        slot,
+                        plugin.name()
+                    );
+                }
+            }
+            measure.stop();
+            inc_new_counter_debug!(
+                "geyser-plugin-update-accounts-us",
+                measure.as_us() as usize,
+                100000,
+                100000
+            );
+        }
+        measure2.stop();
+        inc_new_counter_debug!(
+            "geyser-plugin-notify_plugins_of_account_update_batch-us",
+            measure2.as_us() as usize,
+            100000,
+            100000
+        );
+    }
 }
 
 impl Drop for AccountsUpdateNotifierImpl {
@@ -459,3 +1110,149 @@ impl Drop for AccountsUpdateNotifierImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queued_update(
+        pubkey: Pubkey,
+        slot: Slot,
+        write_version: u64,
+        is_startup: bool,
+    ) -> QueuedAccountUpdate {
+        QueuedAccountUpdate {
+            slot,
+            pubkey,
+            account: AccountSharedData::default(),
+            txn: None,
+            write_version,
+            is_startup,
+            enqueue_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn batcher_flushes_on_size_threshold() {
+        let mut batcher = AccountUpdateBatcher::default();
+        let pubkey = Pubkey::new_unique();
+
+        let mut flushed = None;
+        for write_version in 0..ACCOUNT_UPDATE_BATCH_SIZE as u64 {
+            flushed = batcher.push(queued_update(pubkey, 1, write_version, false));
+        }
+
+        let batch = flushed.expect("the batch should flush once the size threshold is hit");
+        assert_eq!(batch.len(), ACCOUNT_UPDATE_BATCH_SIZE);
+    }
+
+    #[test]
+    fn batcher_flushes_previous_group_on_key_change() {
+        let mut batcher = AccountUpdateBatcher::default();
+        let pubkey = Pubkey::new_unique();
+
+        assert!(batcher.push(queued_update(pubkey, 1, 0, false)).is_none());
+        assert!(batcher.push(queued_update(pubkey, 1, 1, false)).is_none());
+        let flushed = batcher
+            .push(queued_update(pubkey, 2, 2, false))
+            .expect("a new (slot, is_startup) key should flush the previous group");
+
+        assert_eq!(flushed.len(), 2);
+        assert!(flushed.iter().all(|update| update.slot == 1));
+    }
+
+    #[test]
+    fn batcher_take_if_stale_respects_the_window() {
+        let mut batcher = AccountUpdateBatcher::default();
+        let pubkey = Pubkey::new_unique();
+        batcher.push(queued_update(pubkey, 1, 0, false));
+
+        assert!(batcher.take_if_stale().is_none());
+
+        batcher.oldest_enqueued_at = Instant::now().checked_sub(ACCOUNT_UPDATE_BATCH_WINDOW);
+        let flushed = batcher
+            .take_if_stale()
+            .expect("a batch older than the window should flush");
+        assert_eq!(flushed.len(), 1);
+    }
+
+    #[test]
+    fn group_by_batch_key_preserves_order_within_each_group() {
+        let pubkey_a = Pubkey::new_unique();
+        let pubkey_b = Pubkey::new_unique();
+        let updates = vec![
+            queued_update(pubkey_a, 1, 0, false),
+            queued_update(pubkey_b, 2, 1, false),
+            queued_update(pubkey_a, 1, 2, false),
+        ];
+
+        let groups = AccountsUpdateNotifierImpl::group_by_batch_key(updates);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, 1);
+        let slot_one_versions: Vec<u64> = groups[0].2.iter().map(|u| u.write_version).collect();
+        assert_eq!(slot_one_versions, vec![0, 2]);
+        assert_eq!(groups[1].0, 2);
+    }
+
+    #[test]
+    fn order_and_group_for_flush_corrects_an_out_of_order_drain() {
+        let pubkey = Pubkey::new_unique();
+        // A HashMap drain has no defined order, so simulate one handing back
+        // the newer slot before the older one.
+        let updates = vec![
+            queued_update(pubkey, 6, 105, false),
+            queued_update(pubkey, 5, 100, false),
+        ];
+
+        let groups = AccountsUpdateNotifierImpl::order_and_group_for_flush(updates);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, 5, "the older slot must be dispatched first");
+        assert_eq!(groups[1].0, 6);
+    }
+
+    #[test]
+    fn order_and_group_for_flush_keeps_write_versions_ascending_per_account() {
+        let pubkey = Pubkey::new_unique();
+        let updates = vec![
+            queued_update(pubkey, 1, 2, false),
+            queued_update(pubkey, 1, 0, false),
+            queued_update(pubkey, 1, 1, false),
+        ];
+
+        let groups = AccountsUpdateNotifierImpl::order_and_group_for_flush(updates);
+
+        assert_eq!(groups.len(), 1);
+        let write_versions: Vec<u64> = groups[0].2.iter().map(|u| u.write_version).collect();
+        assert_eq!(write_versions, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn shard_for_bytes_is_deterministic_and_in_range() {
+        let pubkey = Pubkey::new_unique();
+        let num_shards = 7;
+
+        let shard = AsyncAccountsDispatch::shard_for_pubkey(&pubkey, num_shards);
+
+        assert!(shard < num_shards);
+        assert_eq!(
+            shard,
+            AsyncAccountsDispatch::shard_for_pubkey(&pubkey, num_shards)
+        );
+    }
+
+    #[test]
+    fn shard_for_bytes_can_land_on_every_shard() {
+        // `Pubkey::new_unique` leads with a big-endian counter, so its first
+        // byte stays zero and every such key would land on shard 0; use keys
+        // whose leading bytes vary instead.
+        let num_shards = 4;
+        let mut seen = [false; 4];
+        for byte in 0..=u8::MAX {
+            let pubkey = Pubkey::new_from_array([byte; 32]);
+            seen[AsyncAccountsDispatch::shard_for_pubkey(&pubkey, num_shards)] = true;
+        }
+        assert!(seen.iter().all(|&hit| hit));
+    }
+}